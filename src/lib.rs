@@ -1,3 +1,38 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Finds the representative of `p` within `parent`, compressing the path to it.
+fn find_root(parent: &mut [usize], mut p: usize) -> usize {
+    let mut r = p;
+    while r != parent[r] {
+        r = parent[r];
+    }
+    // Path compression
+    while p != parent[p] {
+        let pp = parent[p];
+        parent[p] = r;
+        p = pp;
+    }
+    r
+}
+
+/// Merges the sets rooted at `i` and `j` (by size), updating `parent`/`size` in
+/// place. Returns `false` without modifying anything if `i == j` already.
+fn union_roots(parent: &mut [usize], size: &mut [usize], i: usize, j: usize) -> bool {
+    if i == j {
+        return false;
+    }
+    // Union by rank
+    if size[i] < size[j] {
+        parent[i] = j;
+        size[j] += size[i];
+    } else {
+        parent[j] = i;
+        size[i] += size[j];
+    }
+    true
+}
+
 /// Union-find data structure with "union by rank" and "path compression" optimizations.
 pub struct UnionFind {
     components: usize,
@@ -14,46 +49,235 @@ impl UnionFind {
         }
     }
 
-    pub fn union(&mut self, p: usize, q: usize) {
+    /// Merges the sets containing `p` and `q`. Returns `true` if they were in
+    /// different sets (and are now merged), or `false` if they were already
+    /// connected.
+    pub fn union(&mut self, p: usize, q: usize) -> bool {
         let i = self.find(p);
         let j = self.find(q);
-        if i == j {
-            return;
-        }
-        // Union by rank
-        if self.size[i] < self.size[j] {
-            self.parent[i] = j;
-            self.size[j] += self.size[i];
+        if union_roots(&mut self.parent, &mut self.size, i, j) {
+            self.components -= 1;
+            true
         } else {
-            self.parent[j] = i;
-            self.size[i] += self.size[j];
+            false
         }
-        self.components -= 1;
     }
 
-    pub fn find(&mut self, mut p: usize) -> usize {
-        let mut r = p;
-        while r != self.parent[r] {
-            r = self.parent[r];
-        }
-        // Path compression
-        while p != self.parent[p] {
-            let pp = self.parent[p];
-            self.parent[p] = r;
-            p = pp;
-        }
-        r
+    pub fn find(&mut self, p: usize) -> usize {
+        find_root(&mut self.parent, p)
     }
 
     pub fn connected(&mut self, p: usize, q: usize) -> bool {
         self.find(p) == self.find(q)
     }
+    /// Returns the number of elements in the set containing `p`.
+    pub fn component_size(&mut self, p: usize) -> usize {
+        let root = self.find(p);
+        self.size[root]
+    }
     pub fn components(&self) -> usize {
         self.components
     }
     pub fn size(&self) -> usize {
         self.parent.len()
     }
+
+    /// Returns every connected component as a list of its member indices.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut buckets: HashMap<usize, Vec<usize>> = HashMap::new();
+        for p in 0..self.parent.len() {
+            let root = self.find(p);
+            buckets.entry(root).or_default().push(p);
+        }
+        buckets.into_values().collect()
+    }
+}
+
+/// Weighted ("potential") union-find that, in addition to connectivity, tracks a
+/// relative offset between each element and its parent. This supports difference
+/// constraints such as "weight(y) - weight(x) == w" that plain `UnionFind` cannot
+/// express.
+pub struct WeightedUnionFind {
+    components: usize,
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    diff: Vec<i64>,
+}
+
+impl WeightedUnionFind {
+    pub fn new(n: usize) -> WeightedUnionFind {
+        WeightedUnionFind {
+            components: n,
+            parent: (0..n).collect(),
+            size: vec![1; n],
+            diff: vec![0; n],
+        }
+    }
+
+    /// Finds the root of `p` and returns `(root, weight(p) - weight(root))`,
+    /// compressing the path and accumulating potentials along the way.
+    fn find(&mut self, p: usize) -> (usize, i64) {
+        if self.parent[p] == p {
+            return (p, 0);
+        }
+        let (root, parent_diff) = self.find(self.parent[p]);
+        self.diff[p] += parent_diff;
+        self.parent[p] = root;
+        (root, self.diff[p])
+    }
+
+    /// Constrains `weight(y) - weight(x)` to be `w`. Returns `true` if `x` and `y`
+    /// were in different sets (and are now merged), or `false` if they were
+    /// already connected (in which case the existing constraint is left as-is).
+    pub fn union(&mut self, x: usize, y: usize, w: i64) -> bool {
+        let (rx, wx) = self.find(x);
+        let (ry, wy) = self.find(y);
+        if rx == ry {
+            return false;
+        }
+        let root_diff = w + wx - wy;
+        if self.size[rx] < self.size[ry] {
+            self.parent[rx] = ry;
+            self.diff[rx] = -root_diff;
+            self.size[ry] += self.size[rx];
+        } else {
+            self.parent[ry] = rx;
+            self.diff[ry] = root_diff;
+            self.size[rx] += self.size[ry];
+        }
+        self.components -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, x: usize, y: usize) -> bool {
+        self.find(x).0 == self.find(y).0
+    }
+
+    /// Returns `weight(y) - weight(x)` if `x` and `y` are in the same set, or
+    /// `None` if they aren't connected.
+    pub fn diff(&mut self, x: usize, y: usize) -> Option<i64> {
+        let (rx, wx) = self.find(x);
+        let (ry, wy) = self.find(y);
+        if rx != ry {
+            return None;
+        }
+        Some(wy - wx)
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+    pub fn size(&self) -> usize {
+        self.parent.len()
+    }
+}
+
+/// A union-find over arbitrary hashable keys, rather than a dense `0..n` index
+/// space. Internal indices are assigned lazily, on first reference to a key, so
+/// callers don't need to coordinate-compress their data up front.
+pub struct HashMapUnionFind<T: Hash + Eq + Clone> {
+    index: HashMap<T, usize>,
+    components: usize,
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl<T: Hash + Eq + Clone> Default for HashMapUnionFind<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq + Clone> HashMapUnionFind<T> {
+    pub fn new() -> HashMapUnionFind<T> {
+        HashMapUnionFind {
+            index: HashMap::new(),
+            components: 0,
+            parent: Vec::new(),
+            size: Vec::new(),
+        }
+    }
+
+    /// Returns the internal index for `key`, assigning it a fresh one (as its
+    /// own singleton set) if this is the first time it's seen.
+    fn index_of(&mut self, key: &T) -> usize {
+        if let Some(&i) = self.index.get(key) {
+            return i;
+        }
+        let i = self.parent.len();
+        self.index.insert(key.clone(), i);
+        self.parent.push(i);
+        self.size.push(1);
+        self.components += 1;
+        i
+    }
+
+    fn find(&mut self, p: usize) -> usize {
+        find_root(&mut self.parent, p)
+    }
+
+    /// Merges the sets containing `x` and `y`, assigning them indices first if
+    /// needed. Returns `true` if they were in different sets (and are now
+    /// merged), or `false` if they were already connected.
+    pub fn union(&mut self, x: &T, y: &T) -> bool {
+        let i = self.index_of(x);
+        let j = self.index_of(y);
+        let i = self.find(i);
+        let j = self.find(j);
+        if union_roots(&mut self.parent, &mut self.size, i, j) {
+            self.components -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn connected(&mut self, x: &T, y: &T) -> bool {
+        let i = self.index_of(x);
+        let j = self.index_of(y);
+        self.find(i) == self.find(j)
+    }
+
+    pub fn component_size(&mut self, x: &T) -> usize {
+        let i = self.index_of(x);
+        let root = self.find(i);
+        self.size[root]
+    }
+
+    pub fn components(&self) -> usize {
+        self.components
+    }
+}
+
+/// Builds a minimum (or, if `maximize` is set, maximum) spanning forest of the
+/// graph on `n` vertices described by `edges` (as `(u, v, weight)` triples),
+/// using Kruskal's algorithm. Returns the selected edges and their total weight.
+pub fn kruskal(
+    n: usize,
+    edges: &[(usize, usize, i64)],
+    maximize: bool,
+) -> (Vec<(usize, usize, i64)>, i64) {
+    let mut sorted_edges = edges.to_vec();
+    if maximize {
+        sorted_edges.sort_by_key(|e| std::cmp::Reverse(e.2));
+    } else {
+        sorted_edges.sort_by_key(|e| e.2);
+    }
+
+    let mut uf = UnionFind::new(n);
+    let mut forest = Vec::new();
+    let mut total_weight = 0;
+    for edge in sorted_edges {
+        if uf.components() == 1 {
+            break;
+        }
+        let (u, v, weight) = edge;
+        if uf.union(u, v) {
+            forest.push(edge);
+            total_weight += weight;
+        }
+    }
+    (forest, total_weight)
 }
 
 #[cfg(test)]
@@ -102,6 +326,115 @@ mod tests {
         check_components(&mut uf, &[&c1, &c2]);
     }
 
+    #[test]
+    fn kruskal_minimum_spanning_forest() {
+        let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 5), (3, 4, 1)];
+        let (forest, weight) = kruskal(5, &edges, false);
+        assert_eq!(forest.len(), 4);
+        assert_eq!(weight, 1 + 2 + 5 + 1);
+    }
+
+    #[test]
+    fn kruskal_maximum_spanning_forest() {
+        let edges = [(0, 1, 4), (1, 2, 1), (0, 2, 2), (2, 3, 5), (3, 4, 1)];
+        let (forest, weight) = kruskal(5, &edges, true);
+        assert_eq!(forest.len(), 4);
+        assert_eq!(weight, 4 + 2 + 5 + 1);
+    }
+
+    #[test]
+    fn kruskal_stops_once_a_single_tree_forms() {
+        let edges = [(0, 1, 1), (1, 2, 1), (0, 2, 1)];
+        let (forest, weight) = kruskal(3, &edges, false);
+        assert_eq!(forest.len(), 2);
+        assert_eq!(weight, 2);
+    }
+
+    #[test]
+    fn hash_map_union_find_basic() {
+        let mut uf: HashMapUnionFind<String> = HashMapUnionFind::new();
+        let (a, b, c, d) = (
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        );
+        assert!(uf.union(&a, &b));
+        assert!(uf.union(&b, &c));
+        assert!(!uf.union(&a, &c));
+        assert!(uf.connected(&a, &c));
+        assert!(!uf.connected(&a, &d));
+        assert_eq!(uf.component_size(&a), 3);
+        assert_eq!(uf.component_size(&d), 1);
+        assert_eq!(uf.components(), 2);
+    }
+
+    #[test]
+    fn weighted_union_find_basic() {
+        let mut uf = WeightedUnionFind::new(5);
+        assert!(uf.union(0, 1, 3)); // weight(1) - weight(0) == 3
+        assert!(uf.union(1, 2, 2)); // weight(2) - weight(1) == 2
+        assert_eq!(uf.diff(0, 1), Some(3));
+        assert_eq!(uf.diff(0, 2), Some(5));
+        assert_eq!(uf.diff(2, 0), Some(-5));
+        assert_eq!(uf.diff(0, 3), None);
+        assert!(!uf.union(0, 2, 5));
+        assert_eq!(uf.components(), 3);
+    }
+
+    #[test]
+    fn weighted_union_find_merges_in_both_directions() {
+        let mut uf = WeightedUnionFind::new(4);
+        uf.union(2, 3, 1); // weight(3) - weight(2) == 1
+        uf.union(1, 2, -2); // weight(2) - weight(1) == -2
+        uf.union(0, 1, 4); // weight(1) - weight(0) == 4
+        assert_eq!(uf.diff(0, 3), Some(4 - 2 + 1));
+        assert!(uf.connected(0, 3));
+        assert_eq!(uf.components(), 1);
+    }
+
+    #[test]
+    fn groups() {
+        let mut uf = UnionFind::new(7);
+        uf.union(0, 1);
+        uf.union(1, 2);
+        uf.union(1, 3);
+        uf.union(1, 4);
+        uf.union(5, 6);
+        let mut groups = uf.groups();
+        for group in groups.iter_mut() {
+            group.sort();
+        }
+        groups.sort();
+        assert_eq!(groups, vec![vec![0, 1, 2, 3, 4], vec![5, 6]]);
+    }
+
+    #[test]
+    fn union_return_value() {
+        let mut uf = UnionFind::new(5);
+        assert!(uf.union(0, 1));
+        assert!(uf.union(1, 2));
+        assert!(!uf.union(0, 2));
+        assert!(!uf.union(2, 0));
+        assert!(uf.union(3, 4));
+        assert!(!uf.union(3, 4));
+        assert_eq!(uf.components(), 2);
+    }
+
+    #[test]
+    fn component_size() {
+        let mut uf = UnionFind::new(7);
+        uf.union(0, 1);
+        uf.union(1, 2);
+        uf.union(1, 3);
+        uf.union(1, 4);
+        uf.union(5, 6);
+        assert_eq!(uf.component_size(0), 5);
+        assert_eq!(uf.component_size(4), 5);
+        assert_eq!(uf.component_size(5), 2);
+        assert_eq!(uf.component_size(6), 2);
+    }
+
     #[test]
     fn simple_2() {
         let mut uf = UnionFind::new(10);